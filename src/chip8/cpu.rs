@@ -1,12 +1,29 @@
+use crate::chip8::display;
+use crate::chip8::instruction::{self, Instruction};
+use crate::chip8::keypad;
+use crate::chip8::memory::Memory;
+use crate::chip8::quirks::Quirks;
 use crate::chip8::ram;
 use rand::prelude::*;
+use std::fs;
+use std::io;
 
 const N_VREGISTERS: usize = 16;
 const STACK_SIZE: usize = 16;
 const VF: usize = 0xF;
 const V0: usize = 0x0;
 
-pub struct CPU {
+/// Address where ROMs are loaded, and where the program counter starts.
+const PROGRAM_START: u16 = 0x200;
+
+/// Version tag for the `save_state`/`load_state` binary format, bumped on layout changes.
+///
+/// Unused until `load_state`/`save_state` are wired into a front-end; allowed
+/// rather than wired up here.
+#[allow(dead_code)]
+const SAVE_STATE_VERSION: u8 = 1;
+
+pub struct CPU<M: Memory> {
     stack: [u16; STACK_SIZE],
     v_reg: [u8; N_VREGISTERS],
     i_reg: u16,
@@ -15,24 +32,203 @@ pub struct CPU {
     program_counter: u16,
     stack_pointer: u8,
     rng: ThreadRng,
-    ram: ram::RAM,
+    ram: M,
+    display: display::Display,
+    keypad: keypad::Keypad,
+    quirks: Quirks,
 }
 
-impl CPU {
-    /// Create a new CPU instance.
-    /// Every component is started at `0`.
-    pub fn new() -> Self {
+impl<M: Memory> CPU<M> {
+    /// Create a new CPU instance around the given memory, with the given quirks
+    /// configuration. The program counter starts at `PROGRAM_START`; every other
+    /// register, the stack and the timers are started at `0`.
+    pub fn new(ram: M, quirks: Quirks) -> Self {
         CPU {
             stack: [0; STACK_SIZE],
             v_reg: [0; N_VREGISTERS],
             i_reg: 0,
             delay_timer: 0,
             sound_timer: 0,
-            program_counter: 0,
+            program_counter: PROGRAM_START,
             stack_pointer: 0,
             rng: rand::thread_rng(),
-            ram: ram::RAM::new(),
+            ram,
+            display: display::Display::new(),
+            keypad: keypad::Keypad::new(),
+            quirks,
+        }
+    }
+
+    /// A read-only view of the display's framebuffer, for a front-end to render.
+    ///
+    /// This and the following few methods make up the embedding API consumed
+    /// by a front-end (rendering, input, save-state UI); this CLI doesn't have
+    /// one yet, so they're allowed rather than wired up here.
+    #[allow(dead_code)]
+    pub fn display(&self) -> &display::Display {
+        &self.display
+    }
+
+    /// Mark the key `key` as pressed, for a front-end to drive.
+    #[allow(dead_code)]
+    pub fn press_key(&mut self, key: u8) {
+        self.keypad.press(key);
+    }
+
+    /// Mark the key `key` as released, for a front-end to drive.
+    #[allow(dead_code)]
+    pub fn release_key(&mut self, key: u8) {
+        self.keypad.release(key);
+    }
+
+    /// Load a ROM into memory, starting at `PROGRAM_START`.
+    ///
+    /// Returns an error, rather than panicking, if `rom` is too large to fit
+    /// in the address space remaining after `PROGRAM_START`.
+    pub fn load_rom(&mut self, rom: &[u8]) -> io::Result<()> {
+        let capacity = self.ram.dump().len() - PROGRAM_START as usize;
+        if rom.len() > capacity {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "ROM is too large: {} bytes, but only {} bytes are available",
+                    rom.len(),
+                    capacity
+                ),
+            ));
+        }
+        for (offset, &byte) in rom.iter().enumerate() {
+            self.ram.write(PROGRAM_START + offset as u16, byte);
         }
+        Ok(())
+    }
+
+    /// Fetch, decode and execute a single instruction.
+    ///
+    /// Reads the 16-bit big-endian opcode at the program counter, advances the
+    /// program counter by 2, then dispatches the opcode.
+    pub fn step(&mut self) {
+        let opcode = (self.ram.read(self.program_counter) as u16) << 8
+            | self.ram.read(self.program_counter + 1) as u16;
+        self.program_counter += 2;
+        self.cycle(opcode);
+    }
+
+    /// Decrement the delay and sound timers toward zero.
+    ///
+    /// Intended to be driven at 60 Hz, independently of instruction throughput.
+    pub fn tick_timers(&mut self) {
+        self.delay_timer = self.delay_timer.saturating_sub(1);
+        self.sound_timer = self.sound_timer.saturating_sub(1);
+    }
+
+    /// Whether the sound timer is currently active, for a front-end to drive a beep.
+    #[allow(dead_code)]
+    pub fn sound_active(&self) -> bool {
+        self.sound_timer > 0
+    }
+
+    /// Serialize the full machine state to a compact binary blob at `path`.
+    ///
+    /// The RNG is not part of the blob: `load_state` reseeds it rather than restoring it.
+    #[allow(dead_code)]
+    pub fn save_state(&self, path: &str) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        bytes.push(SAVE_STATE_VERSION);
+        for &addr in self.stack.iter() {
+            bytes.extend_from_slice(&addr.to_be_bytes());
+        }
+        bytes.extend_from_slice(&self.v_reg);
+        bytes.extend_from_slice(&self.i_reg.to_be_bytes());
+        bytes.push(self.delay_timer);
+        bytes.push(self.sound_timer);
+        bytes.extend_from_slice(&self.program_counter.to_be_bytes());
+        bytes.push(self.stack_pointer);
+        bytes.extend(self.display.pixels().iter().map(|&pixel| pixel as u8));
+        bytes.extend(self.ram.dump());
+        fs::write(path, bytes)
+    }
+
+    /// Restore the full machine state from a blob written by `save_state`.
+    ///
+    /// The RNG is reseeded rather than restored. Returns an error, rather than
+    /// panicking, if `path` does not contain a well-formed blob of the expected
+    /// length for this machine's memory backend.
+    #[allow(dead_code)]
+    pub fn load_state(&mut self, path: &str) -> io::Result<()> {
+        let bytes = fs::read(path)?;
+
+        let n_pixels = display::WIDTH * display::HEIGHT;
+        let expected_len = 1
+            + STACK_SIZE * 2
+            + N_VREGISTERS
+            + 2
+            + 1
+            + 1
+            + 2
+            + 1
+            + n_pixels
+            + self.ram.dump().len();
+        if bytes.len() != expected_len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "malformed save-state: expected {} bytes, got {}",
+                    expected_len,
+                    bytes.len()
+                ),
+            ));
+        }
+
+        let mut cursor = 0;
+
+        let version = bytes[cursor];
+        cursor += 1;
+        if version != SAVE_STATE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported save-state version {}", version),
+            ));
+        }
+
+        for slot in self.stack.iter_mut() {
+            *slot = u16::from_be_bytes([bytes[cursor], bytes[cursor + 1]]);
+            cursor += 2;
+        }
+
+        self.v_reg.copy_from_slice(&bytes[cursor..cursor + N_VREGISTERS]);
+        cursor += N_VREGISTERS;
+
+        self.i_reg = u16::from_be_bytes([bytes[cursor], bytes[cursor + 1]]);
+        cursor += 2;
+
+        self.delay_timer = bytes[cursor];
+        cursor += 1;
+        self.sound_timer = bytes[cursor];
+        cursor += 1;
+
+        self.program_counter = u16::from_be_bytes([bytes[cursor], bytes[cursor + 1]]);
+        cursor += 2;
+
+        self.stack_pointer = bytes[cursor];
+        cursor += 1;
+
+        let pixels: Vec<bool> = bytes[cursor..cursor + n_pixels]
+            .iter()
+            .map(|&byte| byte != 0)
+            .collect();
+        self.display.restore(&pixels);
+        cursor += n_pixels;
+
+        self.ram.restore(&bytes[cursor..]);
+        self.rng = rand::thread_rng();
+
+        Ok(())
+    }
+
+    /// Clear the display.
+    fn clear_display(&mut self) {
+        self.display.clear();
     }
 
     /// Return from a subroutine.
@@ -94,9 +290,9 @@ impl CPU {
         self.v_reg[x_idx as usize] = value;
     }
 
-    /// Add `value` to the current value of the register `x_idx`.
+    /// Add `value` to the current value of the register `x_idx`, wrapping on overflow.
     fn add_x_value(&mut self, x_idx: u8, value: u8) {
-        self.v_reg[x_idx as usize] += value;
+        self.v_reg[x_idx as usize] = self.v_reg[x_idx as usize].wrapping_add(value);
     }
 
     /// Store the value of the register `y_idx` in the register `x_idx`.
@@ -139,44 +335,54 @@ impl CPU {
 
     /// Subtract the values in registers `x_idx` and `y_idx`, storing the result in `x_idx`.
     ///
-    /// If the value of the register `x_idx` is greater than `y_idx`,
-    /// then the `VF` register is set to `1`, otherwise it is set to `0`.
+    /// If the value of the register `x_idx` is greater than or equal to `y_idx` (no
+    /// borrow), then the `VF` register is set to `1`, otherwise it is set to `0`.
     fn sub_xy(&mut self, x_idx: u8, y_idx: u8) {
-        if self.v_reg[x_idx as usize] > self.v_reg[y_idx as usize] {
+        if self.v_reg[x_idx as usize] >= self.v_reg[y_idx as usize] {
             self.v_reg[VF] = 1;
         } else {
             self.v_reg[VF] = 0;
         }
-        self.v_reg[x_idx as usize] -= self.v_reg[y_idx as usize];
+        self.v_reg[x_idx as usize] = self.v_reg[x_idx as usize].wrapping_sub(self.v_reg[y_idx as usize]);
     }
 
     /// Perform a bitwise-shift *right* on the value of the register `x_idx`.
     ///
-    /// If the least-significant bit of the register `x_idx` is `1` then VF is set to `1`,
+    /// If `quirks.shift_copies_y` is set, `y_idx` is copied into `x_idx` first
+    /// (original COSMAC VIP behavior); otherwise `x_idx` is shifted in place.
+    /// If the least-significant bit of the shifted register is `1` then VF is set to `1`,
     /// otherwise it is set to `0`.
-    fn shr_x(&mut self, x_idx: u8) {
+    fn shr_x(&mut self, x_idx: u8, y_idx: u8) {
+        if self.quirks.shift_copies_y {
+            self.v_reg[x_idx as usize] = self.v_reg[y_idx as usize];
+        }
         self.v_reg[VF] = self.v_reg[x_idx as usize] & 0x1;
         self.v_reg[x_idx as usize] >>= 1;
     }
 
     /// Subtracts the values in registers `y_idx` and `x_idx`, storing the result in `x_idx`.
     ///
-    /// If the value of the register `y_idx` is greater than `x_idx`,
-    /// then the `VF` register is set to `1`, otherwise it is set to `0`.
+    /// If the value of the register `y_idx` is greater than or equal to `x_idx` (no
+    /// borrow), then the `VF` register is set to `1`, otherwise it is set to `0`.
     fn subn_xy(&mut self, x_idx: u8, y_idx: u8) {
-        if self.v_reg[y_idx as usize] > self.v_reg[x_idx as usize] {
+        if self.v_reg[y_idx as usize] >= self.v_reg[x_idx as usize] {
             self.v_reg[VF] = 1;
         } else {
             self.v_reg[VF] = 0;
         }
-        self.v_reg[x_idx as usize] = self.v_reg[y_idx as usize] - self.v_reg[x_idx as usize];
+        self.v_reg[x_idx as usize] = self.v_reg[y_idx as usize].wrapping_sub(self.v_reg[x_idx as usize]);
     }
 
     /// Perform a bitwise-shift *left* on the value of the register `x_idx`.
     ///
-    /// If the most-significant bit of the register `x_idx` is `1` then VF is set to `1`,
+    /// If `quirks.shift_copies_y` is set, `y_idx` is copied into `x_idx` first
+    /// (original COSMAC VIP behavior); otherwise `x_idx` is shifted in place.
+    /// If the most-significant bit of the shifted register is `1` then VF is set to `1`,
     /// otherwise it is set to `0`.
-    fn shl_x(&mut self, x_idx: u8) {
+    fn shl_x(&mut self, x_idx: u8, y_idx: u8) {
+        if self.quirks.shift_copies_y {
+            self.v_reg[x_idx as usize] = self.v_reg[y_idx as usize];
+        }
         self.v_reg[VF] = self.v_reg[x_idx as usize] >> 7;
         self.v_reg[x_idx as usize] <<= 1;
     }
@@ -195,11 +401,17 @@ impl CPU {
         self.i_reg = addr;
     }
 
-    /// Jump to the location `addr + V0`.
+    /// Jump to the location `addr + V0` (or `addr + VX`, if `quirks.jump_offset_uses_vx`
+    /// is set).
     ///
-    /// The program counter is set to the resulting sum of `addr` and `V0`.
-    fn jmp_addr_offset(&mut self, addr: u16) {
-        self.program_counter = addr + self.v_reg[V0] as u16;
+    /// The program counter is set to the resulting sum.
+    fn jmp_addr_offset(&mut self, addr: u16, x_idx: u8) {
+        let offset_reg = if self.quirks.jump_offset_uses_vx {
+            x_idx as usize
+        } else {
+            V0
+        };
+        self.program_counter = addr.wrapping_add(self.v_reg[offset_reg] as u16);
     }
 
     /// Generate a random value between `0` and `255`,
@@ -210,23 +422,48 @@ impl CPU {
         self.v_reg[x_idx as usize] = r_num & value;
     }
 
-    /// TODO
+    /// Draw an `n`-byte sprite starting at address `I` to the display, at position
+    /// `(v_reg[x_idx], v_reg[y_idx])`.
+    ///
+    /// The sprite's rows are read one byte at a time and XORed onto the display,
+    /// MSB first, wrapping around the screen edges. `VF` is set to `1` if this XOR
+    /// causes any set pixel to be turned off, and to `0` otherwise.
     fn draw(&mut self, x_idx: u8, y_idx: u8, n: u8) {
-        let start = self.i_reg as usize;
-        let end = start + (n as usize);
-        for addr in start..end {
-            // self.ram.read(addr)
+        let x_start = self.v_reg[x_idx as usize] as usize % display::WIDTH;
+        let y_start = self.v_reg[y_idx as usize] as usize % display::HEIGHT;
+        self.v_reg[VF] = 0;
+
+        for row in 0..(n as usize) {
+            let sprite_byte = self.ram.read(self.i_reg + row as u16);
+            for bit in 0..8 {
+                let sprite_pixel = (sprite_byte >> (7 - bit)) & 0x1 == 1;
+                let x = (x_start + bit) % display::WIDTH;
+                let y = (y_start + row) % display::HEIGHT;
+                if self.display.xor(x, y, sprite_pixel) {
+                    self.v_reg[VF] = 1;
+                }
+            }
         }
     }
 
-    /// TODO
+    /// Skip the next instruction if the key numbered `v_reg[x_idx] & 0xF` is pressed.
+    ///
+    /// If the key is pressed, the program counter is incremented by 2.
     fn skip_key_pressed(&mut self, x_idx: u8) {
-        unimplemented!()
+        let key = self.v_reg[x_idx as usize] & 0xF;
+        if self.keypad.is_pressed(key) {
+            self.program_counter += 2;
+        }
     }
 
-    /// TODO
+    /// Skip the next instruction if the key numbered `v_reg[x_idx] & 0xF` is not pressed.
+    ///
+    /// If the key is not pressed, the program counter is incremented by 2.
     fn skip_key_not_pressed(&mut self, x_idx: u8) {
-        unimplemented!()
+        let key = self.v_reg[x_idx as usize] & 0xF;
+        if !self.keypad.is_pressed(key) {
+            self.program_counter += 2;
+        }
     }
 
     /// Read the value from the delay timer into the register `x_idx`.
@@ -234,9 +471,15 @@ impl CPU {
         self.v_reg[x_idx as usize] = self.delay_timer;
     }
 
-    /// TODO
+    /// Wait for a key press, storing the pressed key's index in the register `x_idx`.
+    ///
+    /// This blocks without busy-spinning the CPU: while no key is pressed, the program
+    /// counter is rewound by 2 so the same instruction re-executes next cycle.
     fn wait_keypress(&mut self, x_idx: u8) {
-        unimplemented!()
+        match self.keypad.pressed_key() {
+            Some(key) => self.v_reg[x_idx as usize] = key,
+            None => self.program_counter -= 2,
+        }
     }
 
     /// Write the value of the register `x_idx` into the delay timer.
@@ -251,81 +494,95 @@ impl CPU {
 
     /// Increment the value of the `I` register by the value in the `x_idx` register.
     fn add_i(&mut self, x_idx: u8) {
-        self.i_reg += self.v_reg[x_idx as usize] as u16;
+        self.i_reg = self.i_reg.wrapping_add(self.v_reg[x_idx as usize] as u16);
     }
 
-    /// TODO
+    /// Set the `I` register to the address of the built-in font sprite for the
+    /// low nibble of the value in the register `x_idx`.
     fn set_i_digit(&mut self, x_idx: u8) {
-        unimplemented!()
+        let digit = self.v_reg[x_idx as usize] & 0x0F;
+        self.i_reg = (ram::FONT_BASE + ram::FONT_SPRITE_SIZE * digit as usize) as u16;
     }
 
-    /// TODO
+    /// Store the binary-coded decimal representation of the value in the register
+    /// `x_idx` in memory, starting at the address in `I`.
+    ///
+    /// The hundreds digit is written to `ram[I]`, the tens digit to `ram[I + 1]`,
+    /// and the ones digit to `ram[I + 2]`.
     fn store_bcd(&mut self, x_idx: u8) {
-        unimplemented!()
+        let value = self.v_reg[x_idx as usize];
+        let addr = self.i_reg;
+        self.ram.write(addr, value / 100);
+        self.ram.write(addr + 1, (value / 10) % 10);
+        self.ram.write(addr + 2, value % 10);
     }
 
     /// Write registers from `0` to `x_idx` (inclusive), to memory.
     ///
     /// Writing starts at the address in `I` and progresses in increments (`I`, `I+1`, `I+2`, `...`).
+    /// If `quirks.store_load_increments_i` is set, `I` is left incremented by `x_idx + 1`
+    /// afterward (original COSMAC VIP behavior).
     fn store_registers(&mut self, x_idx: u8) {
         for idx in 0..=(x_idx as usize) {
-            self.ram.write(self.i_reg as usize + idx, self.v_reg[idx]);
+            self.ram
+                .write(self.i_reg.wrapping_add(idx as u16), self.v_reg[idx]);
+        }
+        if self.quirks.store_load_increments_i {
+            self.i_reg = self.i_reg.wrapping_add(x_idx as u16 + 1);
         }
     }
 
     /// Read from memory to registers `0` to `x_idx` (inclusive).
     ///
     /// Reading starts at the address in `I` and progresses in increments (`I`, `I+1`, `I+2`, `...`).
+    /// If `quirks.store_load_increments_i` is set, `I` is left incremented by `x_idx + 1`
+    /// afterward (original COSMAC VIP behavior).
     fn read_registers(&mut self, x_idx: u8) {
         for idx in 0..=(x_idx as usize) {
-            self.v_reg[idx] = self.ram.read(self.i_reg as usize + idx);
+            self.v_reg[idx] = self.ram.read(self.i_reg.wrapping_add(idx as u16));
+        }
+        if self.quirks.store_load_increments_i {
+            self.i_reg = self.i_reg.wrapping_add(x_idx as u16 + 1);
         }
     }
 
     fn cycle(&mut self, opcode: u16) {
-        let op_1 = (opcode & 0xF000) >> 12;
-        let op_2 = (opcode & 0x0F00) >> 8;
-        let op_3 = (opcode & 0x00F0) >> 4;
-        let op_4 = opcode & 0x000F;
-
-        match (op_1, op_2, op_3, op_4) {
-            (0x0, 0x0, 0xE, 0x0) => {
-                // clear the display
-            }
-            (0x0, 0x0, 0xE, 0xE) => self.ret_subroutine(),
-            (0x1, _, _, _) => self.jmp_addr(op_2 | op_3 | op_4),
-            (0x2, _, _, _) => self.call_subroutine(op_2 | op_3 | op_4),
-            (0x3, x_idx, _, _) => self.skip_eq_value(x_idx as u8, (op_3 | op_4) as u8),
-            (0x4, x_idx, _, _) => self.skip_neq_value(x_idx as u8, (op_3 | op_4) as u8),
-            (0x5, x_idx, y_idx, 0x0) => self.skip_eq_xy(x_idx as u8, y_idx as u8),
-            (0x6, x_idx, _, _) => self.set_x_value(x_idx as u8, (op_3 | op_4) as u8),
-            (0x7, x_idx, _, _) => self.add_x_value(x_idx as u8, (op_3 | op_4) as u8),
-            (0x8, x_idx, y_idx, 0x0) => self.store_xy(x_idx as u8, y_idx as u8),
-            (0x8, x_idx, y_idx, 0x1) => self.or_xy(x_idx as u8, y_idx as u8),
-            (0x8, x_idx, y_idx, 0x2) => self.and_xy(x_idx as u8, y_idx as u8),
-            (0x8, x_idx, y_idx, 0x3) => self.xor_xy(x_idx as u8, y_idx as u8),
-            (0x8, x_idx, y_idx, 0x4) => self.add_xy(x_idx as u8, y_idx as u8),
-            (0x8, x_idx, y_idx, 0x5) => self.sub_xy(x_idx as u8, y_idx as u8),
-            (0x8, x_idx, _, 0x6) => self.shr_x(x_idx as u8),
-            (0x8, x_idx, y_idx, 0x7) => self.subn_xy(x_idx as u8, y_idx as u8),
-            (0x8, x_idx, _, 0xE) => self.shl_x(x_idx as u8),
-            (0x9, x_idx, y_idx, 0x0) => self.skip_neq_xy(x_idx as u8, y_idx as u8),
-            (0xA, _, _, _) => self.set_i(op_2 | op_3 | op_4),
-            (0xB, _, _, _) => self.jmp_addr_offset(op_2 | op_3 | op_4),
-            (0xC, x_idx, _, _) => self.rnd_and(x_idx as u8, (op_3 | op_4) as u8),
-            (0xD, x_idx, y_idx, n) => self.draw(x_idx as u8, y_idx as u8, n as u8),
-            (0xE, x_idx, 0x9, 0xE) => self.skip_key_pressed(x_idx as u8),
-            (0xE, x_idx, 0xA, 0x1) => self.skip_key_not_pressed(x_idx as u8),
-            (0xF, x_idx, 0x0, 0x7) => self.read_delay_timer(x_idx as u8),
-            (0xF, x_idx, 0x0, 0xA) => self.wait_keypress(x_idx as u8),
-            (0xF, x_idx, 0x1, 0x5) => self.set_delay_timer(x_idx as u8),
-            (0xF, x_idx, 0x1, 0x8) => self.set_sound_timer(x_idx as u8),
-            (0xF, x_idx, 0x1, 0xE) => self.add_i(x_idx as u8),
-            (0xF, x_idx, 0x2, 0x9) => self.set_i_digit(x_idx as u8),
-            (0xF, x_idx, 0x3, 0x3) => self.store_bcd(x_idx as u8),
-            (0xF, x_idx, 0x5, 0x5) => self.store_registers(x_idx as u8),
-            (0xF, x_idx, 0x6, 0x5) => self.read_registers(x_idx as u8),
-            (_, _, _, _) => panic!("unknown instruction"),
+        match instruction::decode(opcode) {
+            Instruction::ClearDisplay => self.clear_display(),
+            Instruction::ReturnSubroutine => self.ret_subroutine(),
+            Instruction::JumpAddr(addr) => self.jmp_addr(addr),
+            Instruction::CallSubroutine(addr) => self.call_subroutine(addr),
+            Instruction::SkipEqValue(x_idx, value) => self.skip_eq_value(x_idx, value),
+            Instruction::SkipNeqValue(x_idx, value) => self.skip_neq_value(x_idx, value),
+            Instruction::SkipEqXY(x_idx, y_idx) => self.skip_eq_xy(x_idx, y_idx),
+            Instruction::SetXValue(x_idx, value) => self.set_x_value(x_idx, value),
+            Instruction::AddXValue(x_idx, value) => self.add_x_value(x_idx, value),
+            Instruction::StoreXY(x_idx, y_idx) => self.store_xy(x_idx, y_idx),
+            Instruction::OrXY(x_idx, y_idx) => self.or_xy(x_idx, y_idx),
+            Instruction::AndXY(x_idx, y_idx) => self.and_xy(x_idx, y_idx),
+            Instruction::XorXY(x_idx, y_idx) => self.xor_xy(x_idx, y_idx),
+            Instruction::AddXY(x_idx, y_idx) => self.add_xy(x_idx, y_idx),
+            Instruction::SubXY(x_idx, y_idx) => self.sub_xy(x_idx, y_idx),
+            Instruction::ShrX(x_idx, y_idx) => self.shr_x(x_idx, y_idx),
+            Instruction::SubnXY(x_idx, y_idx) => self.subn_xy(x_idx, y_idx),
+            Instruction::ShlX(x_idx, y_idx) => self.shl_x(x_idx, y_idx),
+            Instruction::SkipNeqXY(x_idx, y_idx) => self.skip_neq_xy(x_idx, y_idx),
+            Instruction::SetI(addr) => self.set_i(addr),
+            Instruction::JumpAddrOffset(addr, x_idx) => self.jmp_addr_offset(addr, x_idx),
+            Instruction::RndAnd(x_idx, value) => self.rnd_and(x_idx, value),
+            Instruction::Draw(x_idx, y_idx, n) => self.draw(x_idx, y_idx, n),
+            Instruction::SkipKeyPressed(x_idx) => self.skip_key_pressed(x_idx),
+            Instruction::SkipKeyNotPressed(x_idx) => self.skip_key_not_pressed(x_idx),
+            Instruction::ReadDelayTimer(x_idx) => self.read_delay_timer(x_idx),
+            Instruction::WaitKeypress(x_idx) => self.wait_keypress(x_idx),
+            Instruction::SetDelayTimer(x_idx) => self.set_delay_timer(x_idx),
+            Instruction::SetSoundTimer(x_idx) => self.set_sound_timer(x_idx),
+            Instruction::AddI(x_idx) => self.add_i(x_idx),
+            Instruction::SetIDigit(x_idx) => self.set_i_digit(x_idx),
+            Instruction::StoreBcd(x_idx) => self.store_bcd(x_idx),
+            Instruction::StoreRegisters(x_idx) => self.store_registers(x_idx),
+            Instruction::ReadRegisters(x_idx) => self.read_registers(x_idx),
+            Instruction::Unknown(opcode) => panic!("unknown instruction {:#06X}", opcode),
         }
     }
 }
@@ -336,26 +593,26 @@ mod cpu_tests {
 
     #[test]
     fn call_subroutine() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(ram::RAM::new(), Quirks::default());
         let addr = 0x200;
         cpu.call_subroutine(addr);
         assert_eq!(cpu.stack_pointer, 1);
-        assert_eq!(cpu.stack[cpu.stack_pointer as usize], 0);
+        assert_eq!(cpu.stack[cpu.stack_pointer as usize], PROGRAM_START);
         assert_eq!(cpu.program_counter, addr);
     }
 
     #[test]
     fn return_subroutine() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(ram::RAM::new(), Quirks::default());
         cpu.call_subroutine(0x200);
         cpu.ret_subroutine();
-        assert_eq!(cpu.program_counter, 0);
+        assert_eq!(cpu.program_counter, PROGRAM_START);
         assert_eq!(cpu.stack_pointer, 0);
     }
 
     #[test]
     fn jump_to_address() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(ram::RAM::new(), Quirks::default());
         let addr = 0x200;
         cpu.jmp_addr(addr);
         assert_eq!(cpu.program_counter, addr);
@@ -363,7 +620,7 @@ mod cpu_tests {
 
     #[test]
     fn skip_if_register_eq_value() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(ram::RAM::new(), Quirks::default());
         let old_pc = cpu.program_counter;
         cpu.v_reg[0] = 128;
         cpu.skip_eq_value(0, 127);
@@ -374,7 +631,7 @@ mod cpu_tests {
 
     #[test]
     fn skip_if_register_neq_value() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(ram::RAM::new(), Quirks::default());
         let old_pc = cpu.program_counter;
         cpu.v_reg[0] = 128;
         cpu.skip_neq_value(0, 128);
@@ -385,7 +642,7 @@ mod cpu_tests {
 
     #[test]
     fn skip_if_register_eq_xy() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(ram::RAM::new(), Quirks::default());
         let old_pc = cpu.program_counter;
         cpu.v_reg[0] = 128;
         cpu.v_reg[7] = 128;
@@ -398,7 +655,7 @@ mod cpu_tests {
 
     #[test]
     fn skip_if_register_neq_xy() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(ram::RAM::new(), Quirks::default());
         let old_pc = cpu.program_counter;
         cpu.v_reg[0] = 128;
         cpu.v_reg[7] = 128;
@@ -411,7 +668,7 @@ mod cpu_tests {
 
     #[test]
     fn set_store_register() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(ram::RAM::new(), Quirks::default());
         cpu.set_x_value(0, 128);
         assert_eq!(cpu.v_reg[0], 128);
         cpu.store_xy(0, 1);
@@ -420,10 +677,45 @@ mod cpu_tests {
 
     #[test]
     fn add_value_to_register() {
-        let mut cpu = CPU::new();
+        let mut cpu = CPU::new(ram::RAM::new(), Quirks::default());
         cpu.set_x_value(0, 128);
         assert_eq!(cpu.v_reg[0], 128);
         cpu.add_x_value(0, 127);
         assert_eq!(cpu.v_reg[0], 255);
     }
+
+    #[test]
+    fn save_load_round_trip() {
+        let mut cpu = CPU::new(ram::RAM::new(), Quirks::default());
+        cpu.v_reg[3] = 42;
+        cpu.i_reg = 0x300;
+        cpu.program_counter = 0x204;
+        cpu.ram.write(0x300, 0xAB);
+
+        let path = std::env::temp_dir().join("c8_save_load_round_trip.bin");
+        let path = path.to_str().unwrap();
+        cpu.save_state(path).unwrap();
+
+        let mut restored = CPU::new(ram::RAM::new(), Quirks::default());
+        restored.load_state(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(restored.v_reg[3], 42);
+        assert_eq!(restored.i_reg, 0x300);
+        assert_eq!(restored.program_counter, 0x204);
+        assert_eq!(restored.ram.read(0x300), 0xAB);
+    }
+
+    #[test]
+    fn load_state_rejects_malformed_file() {
+        let path = std::env::temp_dir().join("c8_load_state_rejects_malformed_file.bin");
+        let path = path.to_str().unwrap();
+        std::fs::write(path, [SAVE_STATE_VERSION]).unwrap();
+
+        let mut cpu = CPU::new(ram::RAM::new(), Quirks::default());
+        let result = cpu.load_state(path);
+        std::fs::remove_file(path).unwrap();
+
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,46 @@
+/// Display width, in pixels.
+pub const WIDTH: usize = 64;
+
+/// Display height, in pixels.
+pub const HEIGHT: usize = 32;
+
+/// The CHIP-8 display: a monochrome `WIDTH`x`HEIGHT` framebuffer.
+pub struct Display {
+    pixels: [bool; WIDTH * HEIGHT],
+}
+
+impl Display {
+    /// Create a new, blank display.
+    pub fn new() -> Self {
+        Display {
+            pixels: [false; WIDTH * HEIGHT],
+        }
+    }
+
+    /// Turn every pixel off.
+    pub fn clear(&mut self) {
+        self.pixels = [false; WIDTH * HEIGHT];
+    }
+
+    /// XOR `value` into the pixel at `(x, y)`, returning whether a set pixel was turned off.
+    pub fn xor(&mut self, x: usize, y: usize, value: bool) -> bool {
+        let idx = y * WIDTH + x;
+        let collision = self.pixels[idx] && value;
+        self.pixels[idx] ^= value;
+        collision
+    }
+
+    /// A read-only view of the framebuffer, row-major, `WIDTH` pixels per row.
+    ///
+    /// Unused until a front-end renders it; allowed rather than wired up here.
+    #[allow(dead_code)]
+    pub fn pixels(&self) -> &[bool] {
+        &self.pixels
+    }
+
+    /// Restore the framebuffer from a previous `pixels` dump, for save-state support.
+    #[allow(dead_code)]
+    pub fn restore(&mut self, pixels: &[bool]) {
+        self.pixels.copy_from_slice(pixels);
+    }
+}
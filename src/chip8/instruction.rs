@@ -0,0 +1,142 @@
+/// A decoded CHIP-8 instruction, separate from its execution.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Instruction {
+    ClearDisplay,
+    ReturnSubroutine,
+    JumpAddr(u16),
+    CallSubroutine(u16),
+    SkipEqValue(u8, u8),
+    SkipNeqValue(u8, u8),
+    SkipEqXY(u8, u8),
+    SetXValue(u8, u8),
+    AddXValue(u8, u8),
+    StoreXY(u8, u8),
+    OrXY(u8, u8),
+    AndXY(u8, u8),
+    XorXY(u8, u8),
+    AddXY(u8, u8),
+    SubXY(u8, u8),
+    ShrX(u8, u8),
+    SubnXY(u8, u8),
+    ShlX(u8, u8),
+    SkipNeqXY(u8, u8),
+    SetI(u16),
+    JumpAddrOffset(u16, u8),
+    RndAnd(u8, u8),
+    Draw(u8, u8, u8),
+    SkipKeyPressed(u8),
+    SkipKeyNotPressed(u8),
+    ReadDelayTimer(u8),
+    WaitKeypress(u8),
+    SetDelayTimer(u8),
+    SetSoundTimer(u8),
+    AddI(u8),
+    SetIDigit(u8),
+    StoreBcd(u8),
+    StoreRegisters(u8),
+    ReadRegisters(u8),
+    /// An opcode that does not match any known instruction.
+    Unknown(u16),
+}
+
+/// Decode a 16-bit opcode into an `Instruction`, without executing it.
+pub fn decode(opcode: u16) -> Instruction {
+    let op_1 = (opcode & 0xF000) >> 12;
+    let op_2 = (opcode & 0x0F00) >> 8;
+    let op_3 = (opcode & 0x00F0) >> 4;
+    let op_4 = opcode & 0x000F;
+
+    let addr = (op_2 << 8) | (op_3 << 4) | op_4;
+    let byte = ((op_3 << 4) | op_4) as u8;
+    let x = op_2 as u8;
+    let y = op_3 as u8;
+    let n = op_4 as u8;
+
+    match (op_1, op_2, op_3, op_4) {
+        (0x0, 0x0, 0xE, 0x0) => Instruction::ClearDisplay,
+        (0x0, 0x0, 0xE, 0xE) => Instruction::ReturnSubroutine,
+        (0x1, _, _, _) => Instruction::JumpAddr(addr),
+        (0x2, _, _, _) => Instruction::CallSubroutine(addr),
+        (0x3, _, _, _) => Instruction::SkipEqValue(x, byte),
+        (0x4, _, _, _) => Instruction::SkipNeqValue(x, byte),
+        (0x5, _, _, 0x0) => Instruction::SkipEqXY(x, y),
+        (0x6, _, _, _) => Instruction::SetXValue(x, byte),
+        (0x7, _, _, _) => Instruction::AddXValue(x, byte),
+        (0x8, _, _, 0x0) => Instruction::StoreXY(x, y),
+        (0x8, _, _, 0x1) => Instruction::OrXY(x, y),
+        (0x8, _, _, 0x2) => Instruction::AndXY(x, y),
+        (0x8, _, _, 0x3) => Instruction::XorXY(x, y),
+        (0x8, _, _, 0x4) => Instruction::AddXY(x, y),
+        (0x8, _, _, 0x5) => Instruction::SubXY(x, y),
+        (0x8, _, _, 0x6) => Instruction::ShrX(x, y),
+        (0x8, _, _, 0x7) => Instruction::SubnXY(x, y),
+        (0x8, _, _, 0xE) => Instruction::ShlX(x, y),
+        (0x9, _, _, 0x0) => Instruction::SkipNeqXY(x, y),
+        (0xA, _, _, _) => Instruction::SetI(addr),
+        (0xB, _, _, _) => Instruction::JumpAddrOffset(addr, x),
+        (0xC, _, _, _) => Instruction::RndAnd(x, byte),
+        (0xD, _, _, _) => Instruction::Draw(x, y, n),
+        (0xE, _, 0x9, 0xE) => Instruction::SkipKeyPressed(x),
+        (0xE, _, 0xA, 0x1) => Instruction::SkipKeyNotPressed(x),
+        (0xF, _, 0x0, 0x7) => Instruction::ReadDelayTimer(x),
+        (0xF, _, 0x0, 0xA) => Instruction::WaitKeypress(x),
+        (0xF, _, 0x1, 0x5) => Instruction::SetDelayTimer(x),
+        (0xF, _, 0x1, 0x8) => Instruction::SetSoundTimer(x),
+        (0xF, _, 0x1, 0xE) => Instruction::AddI(x),
+        (0xF, _, 0x2, 0x9) => Instruction::SetIDigit(x),
+        (0xF, _, 0x3, 0x3) => Instruction::StoreBcd(x),
+        (0xF, _, 0x5, 0x5) => Instruction::StoreRegisters(x),
+        (0xF, _, 0x6, 0x5) => Instruction::ReadRegisters(x),
+        _ => Instruction::Unknown(opcode),
+    }
+}
+
+#[cfg(test)]
+mod decode_tests {
+    use super::*;
+
+    #[test]
+    fn decodes_every_opcode_family() {
+        let cases = [
+            (0x00E0, Instruction::ClearDisplay),
+            (0x00EE, Instruction::ReturnSubroutine),
+            (0x1234, Instruction::JumpAddr(0x234)),
+            (0x2345, Instruction::CallSubroutine(0x345)),
+            (0x31AB, Instruction::SkipEqValue(1, 0xAB)),
+            (0x41AB, Instruction::SkipNeqValue(1, 0xAB)),
+            (0x5120, Instruction::SkipEqXY(1, 2)),
+            (0x61AB, Instruction::SetXValue(1, 0xAB)),
+            (0x71AB, Instruction::AddXValue(1, 0xAB)),
+            (0x8120, Instruction::StoreXY(1, 2)),
+            (0x8121, Instruction::OrXY(1, 2)),
+            (0x8122, Instruction::AndXY(1, 2)),
+            (0x8123, Instruction::XorXY(1, 2)),
+            (0x8124, Instruction::AddXY(1, 2)),
+            (0x8125, Instruction::SubXY(1, 2)),
+            (0x8126, Instruction::ShrX(1, 2)),
+            (0x8127, Instruction::SubnXY(1, 2)),
+            (0x812E, Instruction::ShlX(1, 2)),
+            (0x9120, Instruction::SkipNeqXY(1, 2)),
+            (0xA234, Instruction::SetI(0x234)),
+            (0xB234, Instruction::JumpAddrOffset(0x234, 2)),
+            (0xC1AB, Instruction::RndAnd(1, 0xAB)),
+            (0xD123, Instruction::Draw(1, 2, 3)),
+            (0xE19E, Instruction::SkipKeyPressed(1)),
+            (0xE1A1, Instruction::SkipKeyNotPressed(1)),
+            (0xF107, Instruction::ReadDelayTimer(1)),
+            (0xF10A, Instruction::WaitKeypress(1)),
+            (0xF115, Instruction::SetDelayTimer(1)),
+            (0xF118, Instruction::SetSoundTimer(1)),
+            (0xF11E, Instruction::AddI(1)),
+            (0xF129, Instruction::SetIDigit(1)),
+            (0xF133, Instruction::StoreBcd(1)),
+            (0xF155, Instruction::StoreRegisters(1)),
+            (0xF165, Instruction::ReadRegisters(1)),
+            (0x5121, Instruction::Unknown(0x5121)),
+        ];
+
+        for (opcode, expected) in cases {
+            assert_eq!(decode(opcode), expected, "opcode {:#06X}", opcode);
+        }
+    }
+}
@@ -0,0 +1,40 @@
+/// Number of keys on the CHIP-8 hex keyboard.
+pub const N_KEYS: usize = 16;
+
+/// The CHIP-8 hex keyboard: 16 keys, numbered `0x0`-`0xF`, each either pressed or not.
+pub struct Keypad {
+    keys: [bool; N_KEYS],
+}
+
+impl Keypad {
+    /// Create a new keypad, with every key released.
+    pub fn new() -> Self {
+        Keypad {
+            keys: [false; N_KEYS],
+        }
+    }
+
+    /// Mark the key `key` as pressed.
+    ///
+    /// Unused until a front-end drives input; allowed rather than wired up here.
+    #[allow(dead_code)]
+    pub fn press(&mut self, key: u8) {
+        self.keys[key as usize] = true;
+    }
+
+    /// Mark the key `key` as released.
+    #[allow(dead_code)]
+    pub fn release(&mut self, key: u8) {
+        self.keys[key as usize] = false;
+    }
+
+    /// Whether the key `key` is currently pressed.
+    pub fn is_pressed(&self, key: u8) -> bool {
+        self.keys[key as usize]
+    }
+
+    /// The lowest-numbered key currently pressed, if any.
+    pub fn pressed_key(&self) -> Option<u8> {
+        self.keys.iter().position(|&pressed| pressed).map(|idx| idx as u8)
+    }
+}
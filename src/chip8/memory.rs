@@ -0,0 +1,22 @@
+/// A CHIP-8 addressable memory space.
+///
+/// Abstracts `CPU` away from any one concrete memory implementation, so a
+/// front-end can plug in instrumented memories (logging, breakpoints, write-
+/// protected regions other than `0x200`) or a test double.
+pub trait Memory {
+    /// Read a single byte at `addr`.
+    fn read(&self, addr: u16) -> u8;
+
+    /// Write a single byte to `addr`.
+    fn write(&mut self, addr: u16, value: u8);
+
+    /// Dump the full memory contents, for save-state support.
+    fn dump(&self) -> Vec<u8>;
+
+    /// Restore the full memory contents from a previous `dump`.
+    ///
+    /// Unused until `CPU::load_state` is wired into a front-end; allowed
+    /// rather than wired up here.
+    #[allow(dead_code)]
+    fn restore(&mut self, bytes: &[u8]);
+}
@@ -0,0 +1,7 @@
+pub mod cpu;
+pub mod display;
+pub mod instruction;
+pub mod keypad;
+pub mod memory;
+pub mod quirks;
+pub mod ram;
@@ -0,0 +1,25 @@
+/// Configuration for opcodes whose behavior diverges across CHIP-8 interpreters.
+pub struct Quirks {
+    /// `8XY6`/`8XYE`: copy `VY` into `VX` before shifting (original COSMAC VIP
+    /// behavior). When `false`, `VX` is shifted in place instead.
+    pub shift_copies_y: bool,
+
+    /// `FX55`/`FX65`: increment `I` by `X + 1` afterward (original COSMAC VIP
+    /// behavior). When `false`, `I` is left unchanged.
+    pub store_load_increments_i: bool,
+
+    /// `BNNN`: jump to `NNN + VX` instead of `NNN + V0`.
+    pub jump_offset_uses_vx: bool,
+}
+
+impl Default for Quirks {
+    /// Classic COSMAC VIP semantics, since that is what the majority of the
+    /// original game corpus targets.
+    fn default() -> Self {
+        Quirks {
+            shift_copies_y: true,
+            store_load_increments_i: true,
+            jump_offset_uses_vx: false,
+        }
+    }
+}
@@ -1,24 +1,74 @@
+use crate::chip8::memory::Memory;
+
+/// Address where the built-in hex font is stored, in the reserved region below `0x200`.
+pub const FONT_BASE: usize = 0x000;
+
+/// Number of bytes in a single font sprite.
+pub const FONT_SPRITE_SIZE: usize = 5;
+
+/// Built-in hex font, sixteen 5-byte sprites for the digits `0`-`F`.
+const FONTSET: [u8; 16 * FONT_SPRITE_SIZE] = [
+    0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
+    0x20, 0x60, 0x20, 0x20, 0x70, // 1
+    0xF0, 0x10, 0xF0, 0x80, 0xF0, // 2
+    0xF0, 0x10, 0xF0, 0x10, 0xF0, // 3
+    0x90, 0x90, 0xF0, 0x10, 0x10, // 4
+    0xF0, 0x80, 0xF0, 0x10, 0xF0, // 5
+    0xF0, 0x80, 0xF0, 0x90, 0xF0, // 6
+    0xF0, 0x10, 0x20, 0x40, 0x40, // 7
+    0xF0, 0x90, 0xF0, 0x90, 0xF0, // 8
+    0xF0, 0x90, 0xF0, 0x10, 0xF0, // 9
+    0xF0, 0x90, 0xF0, 0x90, 0x90, // A
+    0xE0, 0x90, 0xE0, 0x90, 0xE0, // B
+    0xF0, 0x80, 0x80, 0x80, 0xF0, // C
+    0xE0, 0x90, 0x90, 0x90, 0xE0, // D
+    0xF0, 0x80, 0xF0, 0x80, 0xF0, // E
+    0xF0, 0x80, 0xF0, 0x80, 0x80, // F
+];
+
 pub struct RAM {
     memory: [u8; 4096],
 }
 
 impl RAM {
-    /// Create a new RAM instance.
+    /// Create a new RAM instance, with the built-in hex font loaded at `FONT_BASE`.
     pub fn new() -> Self {
-        RAM { memory: [0; 4096] }
+        let mut ram = RAM { memory: [0; 4096] };
+        ram.load_font();
+        ram
     }
 
+    /// Load the built-in hex font into the reserved region below `0x200`.
+    ///
+    /// Bypasses `write`'s guard, since font data legitimately lives in the reserved
+    /// region and is only ever written here, during initialization.
+    fn load_font(&mut self) {
+        self.memory[FONT_BASE..FONT_BASE + FONTSET.len()].copy_from_slice(&FONTSET);
+    }
+}
+
+impl Memory for RAM {
     /// Read a single byte from memory.
-    pub fn read(&self, addr: usize) -> u8 {
-        return self.memory[addr];
+    fn read(&self, addr: u16) -> u8 {
+        self.memory[addr as usize]
     }
 
     /// Write a single byte to memory.
     /// Panics if the `addr` value is less than `0x200`.
-    pub fn write(&mut self, addr: usize, value: u8) {
+    fn write(&mut self, addr: u16, value: u8) {
         if addr <= 0x1FF {
             panic!("invalid write at {}", addr);
         }
-        self.memory[addr] = value;
+        self.memory[addr as usize] = value;
+    }
+
+    /// Dump the full 4 KiB of memory, for save-state support.
+    fn dump(&self) -> Vec<u8> {
+        self.memory.to_vec()
+    }
+
+    /// Restore the full 4 KiB of memory from a previous `dump`, bypassing `write`'s guard.
+    fn restore(&mut self, bytes: &[u8]) {
+        self.memory.copy_from_slice(bytes);
     }
 }
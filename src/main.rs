@@ -1,8 +1,20 @@
 use std::env;
 use std::fs;
+use std::thread;
+use std::time::{Duration, Instant};
 
 mod chip8;
 
+use chip8::cpu::CPU;
+use chip8::quirks::Quirks;
+use chip8::ram::RAM;
+
+/// Rough instruction throughput for original CHIP-8 interpreters.
+const INSTRUCTIONS_PER_SECOND: u32 = 700;
+
+/// Delay/sound timers always count down at 60 Hz, independently of throughput.
+const TIMER_HZ: u32 = 60;
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() != 2 {
@@ -10,5 +22,25 @@ fn main() {
         return;
     }
     let program = fs::read(&args[1]).expect("something went wrong when reading the file");
-    println!("{:?}", program);
+
+    let mut cpu = CPU::new(RAM::new(), Quirks::default());
+    if let Err(e) = cpu.load_rom(&program) {
+        eprintln!("failed to load ROM: {e}");
+        return;
+    }
+
+    let instruction_period = Duration::from_secs_f64(1.0 / INSTRUCTIONS_PER_SECOND as f64);
+    let timer_period = Duration::from_secs_f64(1.0 / TIMER_HZ as f64);
+    let mut last_timer_tick = Instant::now();
+
+    loop {
+        cpu.step();
+
+        if last_timer_tick.elapsed() >= timer_period {
+            cpu.tick_timers();
+            last_timer_tick = Instant::now();
+        }
+
+        thread::sleep(instruction_period);
+    }
 }